@@ -8,6 +8,7 @@ use ethers::{
 	providers::{Http, Middleware},
 	types::BlockId,
 };
+use futures::stream::{self, StreamExt};
 use geth_primitives::CodecHeader;
 use ismp::messaging::Keccak256;
 use sp_core::H256;
@@ -15,17 +16,27 @@ use std::{fmt::Debug, marker::PhantomData, sync::Arc};
 use sync_committee_primitives::constants::BlsPublicKey;
 use tracing::{instrument, trace};
 
+/// Fallback used when constructing a [`BscPosProver`] for a host whose `EvmConfig` doesn't set
+/// `query_batch_size`, mirroring the default `EvmClient` falls back to for the same field.
+pub const DEFAULT_QUERY_BATCH_SIZE: u64 = 10;
+
 #[derive(Clone)]
 pub struct BscPosProver<C: Config> {
 	/// Execution Rpc client
 	pub client: Arc<Provider<Http>>,
+	/// Maximum number of in-flight `eth_getBlockByNumber` requests when fetching epoch ancestry
+	/// concurrently. Sourced from the host chain's `EvmConfig::query_batch_size`.
+	pub query_batch_size: u64,
 	/// Phamtom data
 	_phantom_data: PhantomData<C>,
 }
 
 impl<C: Config> BscPosProver<C> {
-	pub fn new(client: Provider<Http>) -> Self {
-		Self { client: Arc::new(client), _phantom_data: PhantomData }
+	/// `query_batch_size` should come from the host chain's `EvmConfig::query_batch_size`
+	/// (falling back to [`DEFAULT_QUERY_BATCH_SIZE`] when unset), so ancestry-fetch concurrency
+	/// follows the same operator-configured limit as the rest of the EVM client.
+	pub fn new(client: Provider<Http>, query_batch_size: u64) -> Self {
+		Self { client: Arc::new(client), query_batch_size, _phantom_data: PhantomData }
 	}
 
 	pub async fn fetch_header<T: Into<BlockId> + Send + Sync + Debug + Copy>(
@@ -82,7 +93,7 @@ impl<C: Config> BscPosProver<C> {
 		let epoch_header_number = epoch * EPOCH_LENGTH;
 		// If we are still in authority rotation period get the epoch header ancestry alongside
 		// update only if the finalized header is not the epoch block
-		let rotation_block = get_rotation_block(epoch_header_number, validator_size) - 1;
+		let rotation_block = get_rotation_block(epoch_header_number, validator_size)? - 1;
 		if (attested_header.number.low_u64() >= epoch_header_number + 2 &&
             attested_header.number.low_u64() <= rotation_block &&
             source_header.number.low_u64() > epoch_header_number) ||
@@ -91,17 +102,8 @@ impl<C: Config> BscPosProver<C> {
             // We will skip such updates.
             (fetch_val_set_change && source_header.number.low_u64() > epoch_header_number)
 		{
-			let mut header =
-				self.fetch_header(source_header.parent_hash).await?.ok_or_else(|| {
-					anyhow!("header block could not be fetched {}", source_header.parent_hash)
-				})?;
-			epoch_header_ancestry.insert(0, header.clone());
-			while header.number.low_u64() > epoch_header_number {
-				header = self.fetch_header(header.parent_hash).await?.ok_or_else(|| {
-					anyhow!("header block could not be fetched {}", header.parent_hash)
-				})?;
-				epoch_header_ancestry.insert(0, header.clone());
-			}
+			epoch_header_ancestry =
+				self.fetch_epoch_ancestry::<I>(epoch_header_number, &source_header).await?;
 		}
 
 		let source_header_number = source_header.number.low_u64();
@@ -119,40 +121,190 @@ impl<C: Config> BscPosProver<C> {
 		Ok(Some(bsc_client_update))
 	}
 
+	/// Fetch the ancestry of headers from `epoch_header_number` (inclusive) up to but excluding
+	/// `source_header`, ordered from oldest to newest.
+	///
+	/// Headers are fetched concurrently by block number, `query_batch_size` at a time, which is
+	/// considerably faster than walking `parent_hash` one RPC call at a time for a full epoch.
+	/// The resulting chain is verified by checking that each header's `parent_hash` matches the
+	/// keccak hash of its predecessor; if the by-number fetch can't be served (e.g. the RPC
+	/// doesn't support it, or returns a gap) we fall back to the serial parent-hash walk.
+	async fn fetch_epoch_ancestry<I: Keccak256>(
+		&self,
+		epoch_header_number: u64,
+		source_header: &CodecHeader,
+	) -> Result<Vec<CodecHeader>, anyhow::Error> {
+		let source_header_number = source_header.number.low_u64();
+		let range = epoch_header_number..source_header_number;
+
+		if let Some(ancestry) = self.try_fetch_epoch_ancestry_batched(range.clone()).await? {
+			verify_ancestry_chain::<I>(source_header, &ancestry)?;
+			return Ok(ancestry);
+		}
+
+		trace!(target: "bsc-prover", "falling back to serial ancestry walk for epoch header {epoch_header_number}");
+		let mut epoch_header_ancestry = vec![];
+		let mut header = self.fetch_header(source_header.parent_hash).await?.ok_or_else(|| {
+			anyhow!("header block could not be fetched {}", source_header.parent_hash)
+		})?;
+		epoch_header_ancestry.insert(0, header.clone());
+		while header.number.low_u64() > epoch_header_number {
+			header = self.fetch_header(header.parent_hash).await?.ok_or_else(|| {
+				anyhow!("header block could not be fetched {}", header.parent_hash)
+			})?;
+			epoch_header_ancestry.insert(0, header.clone());
+		}
+
+		Ok(epoch_header_ancestry)
+	}
+
+	/// Attempt to fetch every header in `range` concurrently by block number, `query_batch_size`
+	/// requests at a time. Returns `Ok(None)` when the RPC can't serve one of the lookups (e.g. a
+	/// pruned node), signalling that callers should fall back to the serial parent-hash walk.
+	async fn try_fetch_epoch_ancestry_batched(
+		&self,
+		range: std::ops::Range<u64>,
+	) -> Result<Option<Vec<CodecHeader>>, anyhow::Error> {
+		let query_batch_size = self.query_batch_size.max(1) as usize;
+		let results = stream::iter(range)
+			.map(|number| async move { self.fetch_header(number).await })
+			.buffer_unordered(query_batch_size)
+			.collect::<Vec<_>>()
+			.await;
+
+		let mut headers = Vec::with_capacity(results.len());
+		for result in results {
+			match result {
+				Ok(Some(header)) => headers.push(header),
+				// A missing or erroring by-number lookup means this RPC can't reliably serve the
+				// batched path, let the caller fall back to the serial walk.
+				Ok(None) => return Ok(None),
+				Err(_) => return Ok(None),
+			}
+		}
+
+		headers.sort_by_key(|header| header.number.low_u64());
+
+		Ok(Some(headers))
+	}
+
 	pub async fn fetch_finalized_state<I: Keccak256>(
 		&self,
 	) -> Result<(CodecHeader, Vec<BlsPublicKey>), anyhow::Error> {
 		let latest_header = self.latest_header().await?;
-
 		let current_epoch = compute_epoch(latest_header.number.low_u64());
-		let current_epoch_block_number = current_epoch * EPOCH_LENGTH;
 
-		let current_epoch_header =
-			self.fetch_header(current_epoch_block_number).await?.ok_or_else(|| {
-				anyhow!("header block could not be fetched {current_epoch_block_number}")
-			})?;
-		let current_epoch_extra_data = parse_extra::<I, C>(&current_epoch_header)
+		self.fetch_checkpoint_state::<I>(current_epoch).await
+	}
+
+	/// Resolve the epoch header and validator set at an arbitrary historical `epoch`, i.e. the
+	/// block at `epoch * EPOCH_LENGTH`.
+	///
+	/// This lets a consensus client bootstrap from a trusted recent checkpoint instead of
+	/// replaying the chain from genesis; pair it with [`Self::fetch_updates_between`] to produce
+	/// the updates needed to catch the client up from that checkpoint to the present epoch.
+	pub async fn fetch_checkpoint_state<I: Keccak256>(
+		&self,
+		epoch: u64,
+	) -> Result<(CodecHeader, Vec<BlsPublicKey>), anyhow::Error> {
+		let epoch_block_number = epoch * EPOCH_LENGTH;
+
+		let epoch_header = self
+			.fetch_header(epoch_block_number)
+			.await?
+			.ok_or_else(|| anyhow!("header block could not be fetched {epoch_block_number}"))?;
+		let epoch_extra_data = parse_extra::<I, C>(&epoch_header)
 			.map_err(|_| anyhow!("Extra data set not found in header"))?;
 
-		let current_validators = current_epoch_extra_data
+		let validators = epoch_extra_data
 			.validators
 			.into_iter()
 			.map(|val| val.bls_public_key.as_slice().try_into().expect("Infallible"))
 			.collect::<Vec<BlsPublicKey>>();
-		Ok((current_epoch_header, current_validators))
+		Ok((epoch_header, validators))
 	}
+
+	/// Walk every epoch in `[from_epoch, to_epoch)` and produce the ordered `BscClientUpdate`s
+	/// needed to bring a client from `from_epoch` up to `to_epoch`.
+	///
+	/// For each epoch, [`get_rotation_block`] picks the block at which the validator set changes;
+	/// the attested header is taken from just after that rotation so that its vote attestation's
+	/// source/target headers span the rotation, then handed to [`Self::fetch_bsc_update`] with
+	/// `fetch_val_set_change` forced on. Epochs whose attested header carries no vote attestation
+	/// (so no update is produced) are skipped.
+	pub async fn fetch_updates_between<I: Keccak256>(
+		&self,
+		from_epoch: u64,
+		to_epoch: u64,
+	) -> Result<Vec<BscClientUpdate>, anyhow::Error> {
+		let mut updates = vec![];
+
+		for epoch in from_epoch..to_epoch {
+			let (_, validators) = self.fetch_checkpoint_state::<I>(epoch).await?;
+			let validator_size = validators.len() as u64;
+			let epoch_header_number = epoch * EPOCH_LENGTH;
+			let rotation_block = get_rotation_block(epoch_header_number, validator_size)?;
+
+			// Take the attested header a couple of blocks after the rotation point, so its vote
+			// attestation's source/target headers straddle the validator set change.
+			let attested_number = rotation_block + 2;
+			let attested_header = self.fetch_header(attested_number).await?.ok_or_else(|| {
+				anyhow!("header block could not be fetched {attested_number}")
+			})?;
+
+			match self.fetch_bsc_update::<I>(attested_header, validator_size, epoch, true).await? {
+				Some(update) => updates.push(update),
+				None => trace!(
+					target: "bsc-prover",
+					"no vote attestation found while walking epoch {epoch}"
+				),
+			}
+		}
+
+		Ok(updates)
+	}
+}
+
+/// Verify that `ancestry` (oldest to newest) together with `source_header` forms an unbroken
+/// hash chain, i.e. each header's `parent_hash` equals the keccak hash of its predecessor.
+///
+/// This guards against a reorg-ing or misbehaving RPC handing back headers for the requested
+/// block numbers that don't actually chain up to `source_header`.
+fn verify_ancestry_chain<I: Keccak256>(
+	source_header: &CodecHeader,
+	ancestry: &[CodecHeader],
+) -> Result<(), anyhow::Error> {
+	let mut expected_parent_hash = source_header.parent_hash;
+	for header in ancestry.iter().rev() {
+		let hash = header.hash::<I>();
+		if hash != expected_parent_hash {
+			return Err(anyhow!(
+				"epoch ancestry hash chain broken at block {:?}: expected parent hash {expected_parent_hash}, computed {hash}",
+				header.number
+			));
+		}
+		expected_parent_hash = header.parent_hash;
+	}
+
+	Ok(())
 }
 
 // Get the maximum block that can be signed by previous validator set before authority set rotation
 // occurs Validator set change happens at
 // block%EPOCH_LENGTH == validator_size / 2
-pub fn get_rotation_block(mut block: u64, validator_size: u64) -> u64 {
-	loop {
-		if block % EPOCH_LENGTH == (validator_size / 2) {
-			break;
-		}
-		block += 1
+pub fn get_rotation_block(block: u64, validator_size: u64) -> Result<u64, anyhow::Error> {
+	let half_validator_size = validator_size / 2;
+	if half_validator_size >= EPOCH_LENGTH {
+		return Err(anyhow!(
+			"validator_size / 2 ({half_validator_size}) must be less than EPOCH_LENGTH ({EPOCH_LENGTH}), got validator_size {validator_size}"
+		));
+	}
+
+	let base = block - (block % EPOCH_LENGTH);
+	let mut target = base + half_validator_size;
+	if target < block {
+		target += EPOCH_LENGTH;
 	}
 
-	block
+	Ok(target)
 }
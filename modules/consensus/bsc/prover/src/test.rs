@@ -0,0 +1,124 @@
+use crate::{get_rotation_block, verify_ancestry_chain};
+use bsc_verifier::primitives::EPOCH_LENGTH;
+use geth_primitives::CodecHeader;
+use ismp::messaging::Keccak256;
+use sp_core::{keccak_256, H256};
+
+/// The pre-closed-form behaviour of [`get_rotation_block`], kept here so the closed-form
+/// computation can be checked against it across a range of inputs.
+fn brute_force_rotation_block(mut block: u64, validator_size: u64) -> u64 {
+	loop {
+		if block % EPOCH_LENGTH == (validator_size / 2) {
+			break;
+		}
+		block += 1
+	}
+
+	block
+}
+
+#[test]
+fn rejects_misconfigured_validator_size() {
+	assert!(get_rotation_block(0, EPOCH_LENGTH * 2).is_err());
+	assert!(get_rotation_block(0, EPOCH_LENGTH * 2 + 1).is_err());
+}
+
+#[test]
+fn accepts_validator_size_just_under_the_limit() {
+	// validator_size / 2 == EPOCH_LENGTH - 1 is the largest value that still terminates.
+	let validator_size = (EPOCH_LENGTH - 1) * 2;
+	assert!(get_rotation_block(0, validator_size).is_ok());
+}
+
+#[test]
+fn matches_brute_force_loop_when_block_is_already_at_the_rotation_point() {
+	let epoch_header_number = 3 * EPOCH_LENGTH;
+	let validator_size = 42;
+	let block = epoch_header_number + validator_size / 2;
+
+	assert_eq!(
+		get_rotation_block(block, validator_size).unwrap(),
+		brute_force_rotation_block(block, validator_size)
+	);
+	assert_eq!(get_rotation_block(block, validator_size).unwrap(), block);
+}
+
+#[test]
+fn matches_brute_force_loop_when_rotation_wraps_into_the_next_epoch() {
+	let epoch_header_number = 3 * EPOCH_LENGTH;
+	let validator_size = 42;
+	// block is already past this epoch's rotation point, so the target must wrap forward.
+	let block = epoch_header_number + validator_size / 2 + 1;
+
+	assert_eq!(
+		get_rotation_block(block, validator_size).unwrap(),
+		brute_force_rotation_block(block, validator_size)
+	);
+}
+
+#[test]
+fn matches_brute_force_loop_across_a_range_of_inputs() {
+	for epoch in 0..3u64 {
+		for validator_size in [20u64, 21, 41, 100, 101] {
+			for offset in 0..EPOCH_LENGTH.min(50) {
+				let block = epoch * EPOCH_LENGTH + offset;
+				assert_eq!(
+					get_rotation_block(block, validator_size).unwrap(),
+					brute_force_rotation_block(block, validator_size),
+					"block {block}, validator_size {validator_size}"
+				);
+			}
+		}
+	}
+}
+
+/// A `Keccak256` impl that just delegates to `sp_core`'s implementation, for use in tests that
+/// need to hash a [`CodecHeader`] the same way the consensus client code does.
+struct TestHasher;
+
+impl Keccak256 for TestHasher {
+	fn keccak256(bytes: &[u8]) -> H256
+	where
+		Self: Sized,
+	{
+		keccak_256(bytes).into()
+	}
+}
+
+fn header_with(number: u64, parent_hash: H256) -> CodecHeader {
+	CodecHeader { number: number.into(), parent_hash, ..Default::default() }
+}
+
+#[test]
+fn verify_ancestry_chain_accepts_a_well_formed_chain() {
+	let epoch_header = header_with(100, H256::zero());
+	let middle_header = header_with(101, epoch_header.hash::<TestHasher>());
+	let source_header = header_with(102, middle_header.hash::<TestHasher>());
+
+	let ancestry = vec![epoch_header, middle_header];
+	assert!(verify_ancestry_chain::<TestHasher>(&source_header, &ancestry).is_ok());
+}
+
+#[test]
+fn verify_ancestry_chain_rejects_a_tampered_header() {
+	let epoch_header = header_with(100, H256::zero());
+	let middle_header = header_with(101, epoch_header.hash::<TestHasher>());
+	let source_header = header_with(102, middle_header.hash::<TestHasher>());
+
+	// Swap in a header whose parent_hash doesn't match the one below it in the chain.
+	let tampered_middle_header = header_with(101, H256::repeat_byte(0xab));
+	let ancestry = vec![epoch_header, tampered_middle_header];
+
+	assert!(verify_ancestry_chain::<TestHasher>(&source_header, &ancestry).is_err());
+}
+
+#[test]
+fn verify_ancestry_chain_rejects_reordered_headers() {
+	let epoch_header = header_with(100, H256::zero());
+	let middle_header = header_with(101, epoch_header.hash::<TestHasher>());
+	let source_header = header_with(102, middle_header.hash::<TestHasher>());
+
+	// Ancestry must be ordered oldest to newest; swapping the two breaks the chain.
+	let ancestry = vec![middle_header, epoch_header];
+	assert!(verify_ancestry_chain::<TestHasher>(&source_header, &ancestry).is_err());
+}
@@ -15,8 +15,9 @@
 
 //! Tesseract CLI utilities
 
-use crate::{config::Config, logging};
+use crate::{config::Config, dry_run, logging};
 use clap::Parser;
+use futures::future::join_all;
 
 /// Tesseract, the multi-chain ISMP relayer
 #[derive(Parser, Debug)]
@@ -24,6 +25,12 @@ pub struct Cli {
     /// Path to the relayer config file
     #[arg(short, long)]
     config: String,
+    /// Run the relay pipeline up to submission without sending any transaction: pending requests
+    /// are discovered on the source and a state proof for them is built and verified. Decoded
+    /// requests, their computed commitments and the verification outcome are logged. No
+    /// consensus update is fetched and no message is assembled.
+    #[arg(long)]
+    dry_run: bool,
 }
 
 impl Cli {
@@ -35,11 +42,136 @@ impl Cli {
 
         let tesseract_config = toml::from_str::<Config>(&config)?;
 
-        let chain_a = tesseract_config.chain_a.into_client().await?;
-        let chain_b = tesseract_config.chain_b.into_client().await?;
+        let mut chains = Vec::with_capacity(tesseract_config.chains.len());
+        for chain in &tesseract_config.chains {
+            chains.push((chain.name().to_string(), chain.into_client().await?));
+        }
 
-        messaging::relay(chain_a, chain_b).await?;
+        if self.dry_run {
+            tracing::info!(target: "tesseract", "dry-run: relay pipeline will not submit any transactions");
+        }
+
+        let links = relay_topology(&chains, tesseract_config.coordinator.as_deref())?;
+        let dry_run = self.dry_run;
+
+        let tasks = links.into_iter().map(|(name_a, client_a, name_b, client_b)| {
+            tokio::spawn(async move {
+                let result = if dry_run {
+                    dry_run::run(client_a, client_b).await
+                } else {
+                    messaging::relay(client_a, client_b).await
+                };
+                (name_a, name_b, result)
+            })
+        });
+
+        let mut errors = vec![];
+        for task in join_all(tasks).await {
+            let (name_a, name_b, result) = task?;
+            if let Err(err) = result {
+                tracing::error!(target: "tesseract", "relay {name_a} <-> {name_b} stalled: {err:?}");
+                errors.push(err);
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(anyhow::anyhow!("{} relay link(s) failed: {errors:?}", errors.len()));
+        }
 
         Ok(())
     }
 }
+
+/// Derive the chain pairs to relay between for the given topology.
+///
+/// If `coordinator` names one of the configured chains, every other chain relays only against
+/// it (hub-and-spoke). If `coordinator` is `None`, every chain relays against every other chain
+/// (full mesh). A `coordinator` that doesn't match any configured chain name is a misconfiguration
+/// (typo or stale config) and is rejected rather than silently falling back to full mesh.
+fn relay_topology<C: Clone>(
+    chains: &[(String, C)],
+    coordinator: Option<&str>,
+) -> Result<Vec<(String, C, String, C)>, anyhow::Error> {
+    let mut links = vec![];
+
+    let hub = match coordinator {
+        Some(name) => Some(
+            chains
+                .iter()
+                .find(|(chain_name, _)| chain_name == name)
+                .ok_or_else(|| anyhow::anyhow!("coordinator chain '{name}' is not configured"))?,
+        ),
+        None => None,
+    };
+
+    match hub {
+        Some((hub_name, hub_client)) =>
+            for (name, client) in chains {
+                if name == hub_name {
+                    continue;
+                }
+                links.push((hub_name.clone(), hub_client.clone(), name.clone(), client.clone()));
+            },
+        None =>
+            for (i, (name_a, client_a)) in chains.iter().enumerate() {
+                for (name_b, client_b) in &chains[i + 1..] {
+                    links.push((name_a.clone(), client_a.clone(), name_b.clone(), client_b.clone()));
+                }
+            },
+    }
+
+    Ok(links)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::relay_topology;
+
+    #[test]
+    fn full_mesh_when_no_coordinator_is_set() {
+        let chains = vec![
+            ("a".to_string(), 1u8),
+            ("b".to_string(), 2u8),
+            ("c".to_string(), 3u8),
+        ];
+
+        let links = relay_topology(&chains, None).unwrap();
+
+        assert_eq!(links.len(), 3);
+        assert!(links.contains(&("a".to_string(), 1, "b".to_string(), 2)));
+        assert!(links.contains(&("a".to_string(), 1, "c".to_string(), 3)));
+        assert!(links.contains(&("b".to_string(), 2, "c".to_string(), 3)));
+    }
+
+    #[test]
+    fn hub_and_spoke_when_coordinator_matches_a_configured_chain() {
+        let chains = vec![
+            ("a".to_string(), 1u8),
+            ("b".to_string(), 2u8),
+            ("c".to_string(), 3u8),
+        ];
+
+        let links = relay_topology(&chains, Some("b")).unwrap();
+
+        assert_eq!(links.len(), 2);
+        assert!(links.iter().all(|(hub, _, _, _)| hub == "b"));
+        assert!(links.contains(&("b".to_string(), 2, "a".to_string(), 1)));
+        assert!(links.contains(&("b".to_string(), 2, "c".to_string(), 3)));
+    }
+
+    #[test]
+    fn hub_with_no_spokes_yields_no_links() {
+        let chains = vec![("a".to_string(), 1u8)];
+
+        let links = relay_topology(&chains, Some("a")).unwrap();
+
+        assert!(links.is_empty());
+    }
+
+    #[test]
+    fn unresolvable_coordinator_is_rejected_rather_than_falling_back_to_full_mesh() {
+        let chains = vec![("a".to_string(), 1u8), ("b".to_string(), 2u8)];
+
+        assert!(relay_topology(&chains, Some("typo-d")).is_err());
+    }
+}
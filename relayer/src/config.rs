@@ -0,0 +1,81 @@
+// Copyright (C) Polytope Labs Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Tesseract relayer configuration
+
+use evm_common::{optimism::client::OpHost, EvmClient, EvmConfig};
+use serde::{Deserialize, Serialize};
+
+/// Tesseract relayer configuration
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Config {
+    /// Chains to relay ISMP messages between.
+    pub chains: Vec<ChainConfig>,
+    /// Name of the chain (matching a [`ChainConfig::name`]) that acts as the hub for
+    /// hub-and-spoke relaying. When unset, every configured chain relays against every other
+    /// chain (full mesh).
+    pub coordinator: Option<String>,
+}
+
+/// A single chain participating in the relay, alongside the name used to refer to it (e.g. in
+/// `coordinator`) and in logs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChainConfig {
+    /// Human-readable name for this chain.
+    pub name: String,
+    /// Chain-specific client configuration.
+    #[serde(flatten)]
+    pub client: AnyConfig,
+}
+
+impl ChainConfig {
+    /// The configured name of this chain.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Build the client for this chain.
+    pub async fn into_client(&self) -> Result<AnyClient, anyhow::Error> {
+        self.client.clone().into_client().await
+    }
+}
+
+/// Chain-specific client configuration, keyed by which client implementation should be built.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AnyConfig {
+    /// An EVM-compatible chain.
+    Evm(EvmConfig),
+}
+
+impl AnyConfig {
+    /// Build the concrete client for this chain.
+    pub async fn into_client(self) -> Result<AnyClient, anyhow::Error> {
+        match self {
+            AnyConfig::Evm(config) =>
+                Ok(AnyClient::Evm(EvmClient::<OpHost>::new(None, config).await?)),
+        }
+    }
+}
+
+/// A relay-ready client for any of the chain kinds supported by [`AnyConfig`].
+///
+/// Each variant is cheaply cloneable, since the same chain can participate in more than one
+/// relay link (e.g. hub-and-spoke topologies relay the hub against every spoke).
+#[derive(Clone)]
+pub enum AnyClient {
+    /// An EVM-compatible chain client.
+    Evm(EvmClient<OpHost>),
+}
@@ -0,0 +1,180 @@
+// Copyright (C) Polytope Labs Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Dry-run verification for a relay link: discover pending requests, build the state proof a
+//! real relay would submit, and verify it against the destination — without sending any
+//! transaction.
+
+use crate::config::AnyClient;
+use anyhow::anyhow;
+use ethers::providers::Middleware;
+use evm_common::{optimism::client::OpHost, EvmClient};
+use ismp::{
+	consensus::{StateCommitment, StateMachineHeight, StateMachineId},
+	host::StateMachine,
+	messaging::{Keccak256, Proof},
+	router::{Post, Request, RequestResponse},
+	util::hash_request,
+};
+use ismp_solidity_abi::evm_host::{EvmHost, EvmHostEvents};
+use ismp_sync_committee::verify_membership;
+use sp_core::keccak_256;
+use std::str::FromStr;
+use tesseract_primitives::Query;
+
+/// How many trailing blocks of `source` to scan for `PostRequestEvent`s when discovering what a
+/// real relay would submit.
+const LOOKBACK_BLOCKS: u64 = 256;
+
+/// Run the relay pipeline between `chain_a` and `chain_b` up to (but not including) submission:
+/// discover pending requests on the source, build the state proof that would back a submission,
+/// verify it against the destination, and log the outcome instead of sending it on.
+pub async fn run(chain_a: AnyClient, chain_b: AnyClient) -> Result<(), anyhow::Error> {
+	match (chain_a, chain_b) {
+		(AnyClient::Evm(a), AnyClient::Evm(b)) => run_evm_pair(a, b).await,
+	}
+}
+
+async fn run_evm_pair(
+	source: EvmClient<OpHost>,
+	dest: EvmClient<OpHost>,
+) -> Result<(), anyhow::Error> {
+	let at = source.client.get_block_number().await?.as_u64();
+	let dest_at = dest.client.get_block_number().await?.as_u64();
+
+	let requests = pending_post_requests(&source, at).await?;
+	if requests.is_empty() {
+		tracing::info!(
+			target: "tesseract",
+			"dry-run: no pending requests found on source in the last {LOOKBACK_BLOCKS} blocks (source block {at}, destination block {dest_at})"
+		);
+		return Ok(());
+	}
+
+	let queries = requests
+		.iter()
+		.map(|request| Query {
+			source_chain: request.source_chain(),
+			dest_chain: request.dest_chain(),
+			nonce: request.nonce(),
+			commitment: hash_request::<DryRunHasher>(request),
+		})
+		.collect::<Vec<_>>();
+
+	tracing::info!(target: "tesseract", "dry-run: found {} pending request(s) to verify", queries.len());
+
+	let proof = source.query_requests_proof(at, queries.clone()).await?;
+
+	let dest_block = dest
+		.client
+		.get_block(dest_at)
+		.await?
+		.ok_or_else(|| anyhow!("destination block {dest_at} could not be fetched"))?;
+	let state_commitment = StateCommitment {
+		timestamp: dest_block.timestamp.as_u64(),
+		overlay_root: None,
+		state_root: dest_block.state_root,
+	};
+	let proof = Proof {
+		height: StateMachineHeight {
+			id: StateMachineId {
+				state_id: dest.config.state_machine,
+				consensus_state_id: Default::default(),
+			},
+			height: dest_at,
+		},
+		proof,
+	};
+
+	for (query, request) in queries.into_iter().zip(requests.into_iter()) {
+		tracing::info!(
+			target: "tesseract",
+			"dry-run: decoded request {request:?} with commitment {:?}",
+			query.commitment,
+		);
+		let outcome = verify_membership::<DryRunHasher>(
+			RequestResponse::Request(vec![request]),
+			state_commitment,
+			&proof,
+			dest.config.ismp_host,
+		);
+
+		match outcome {
+			Ok(()) => tracing::info!(
+				target: "tesseract",
+				"dry-run: request {:?} (nonce {}) verified against destination at block {dest_at}",
+				query.commitment, query.nonce,
+			),
+			Err(err) => tracing::error!(
+				target: "tesseract",
+				"dry-run: request {:?} (nonce {}) FAILED verification: {err:?}",
+				query.commitment, query.nonce,
+			),
+		}
+	}
+
+	Ok(())
+}
+
+/// Scan the last [`LOOKBACK_BLOCKS`] of `source` for `PostRequestEvent`s emitted by its ISMP
+/// host, decoding each into the [`Request`] it represents.
+///
+/// `Get` requests aren't relayed this way upstream either, so they're skipped here too.
+async fn pending_post_requests(
+	source: &EvmClient<OpHost>,
+	at: u64,
+) -> Result<Vec<Request>, anyhow::Error> {
+	let contract = EvmHost::new(source.config.ismp_host, source.client.clone());
+	let from_block = at.saturating_sub(LOOKBACK_BLOCKS);
+
+	let events = contract.events().from_block(from_block).to_block(at).query().await?;
+
+	let requests = events
+		.into_iter()
+		.filter_map(|event| match event {
+			EvmHostEvents::PostRequestEventFilter(event) => Some(event),
+			_ => None,
+		})
+		.map(|event| {
+			Ok(Request::Post(Post {
+				source: StateMachine::from_str(&String::from_utf8(event.source)?)
+					.map_err(|_| anyhow!("invalid source state machine in PostRequestEvent"))?,
+				dest: StateMachine::from_str(&String::from_utf8(event.dest)?)
+					.map_err(|_| anyhow!("invalid dest state machine in PostRequestEvent"))?,
+				nonce: event.nonce.as_u64(),
+				from: event.from.to_vec(),
+				to: event.to.to_vec(),
+				timeout_timestamp: event.timeout_timestamp.as_u64(),
+				data: event.data.to_vec(),
+				gas_limit: event.gaslimit.as_u64(),
+			}))
+		})
+		.collect::<Result<Vec<_>, anyhow::Error>>()?;
+
+	Ok(requests)
+}
+
+/// `Keccak256` impl backing the dry-run's own hashing and proof verification, mirroring the
+/// production EVM keccak hashing the real relay path uses.
+struct DryRunHasher;
+
+impl Keccak256 for DryRunHasher {
+	fn keccak256(bytes: &[u8]) -> sp_core::H256
+	where
+		Self: Sized,
+	{
+		keccak_256(bytes).into()
+	}
+}